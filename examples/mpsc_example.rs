@@ -50,9 +50,9 @@ fn main() {
     // We will collect all the numbers sent from the producer threads.
     let mut received_numbers = Vec::new();
 
-    // This `while let` loop will automatically break when the channel is empty
-    // AND all senders have been dropped.
-    while let Ok(number) = rx.recv() {
+    // This `for` loop (via `IntoIterator for Receiver`) automatically stops
+    // once the channel is empty and all senders have been dropped.
+    for number in rx {
         received_numbers.push(number);
     }
 
@@ -0,0 +1,15 @@
+//! Channels for inter-thread communication.
+//!
+//! This crate provides two channel modules:
+//!
+//! - [`mpsc`]: multi-producer, single-consumer, either bounded (backed by a
+//!   ring buffer) or unbounded (backed by a chain of fixed-size blocks).
+//! - [`mpmc`]: multi-producer, multi-consumer, bounded, backed by the same
+//!   ring buffer.
+
+mod park;
+mod ring_buf;
+mod unbounded_queue;
+
+pub mod mpmc;
+pub mod mpsc;
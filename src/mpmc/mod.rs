@@ -0,0 +1,171 @@
+mod error;
+
+pub use error::{RecvError, SendError, TryRecvError};
+
+use crate::park;
+use crate::ring_buf::RingBuf;
+use std::sync::Arc;
+
+/// The sending side of a multi-producer, multi-consumer channel.
+pub struct Sender<T> {
+    channel: Arc<RingBuf<T>>,
+}
+
+/// The receiving side of a multi-producer, multi-consumer channel.
+pub struct Receiver<T> {
+    channel: Arc<RingBuf<T>>,
+}
+
+/// Creates a new bounded MPMC channel with a specified capacity.
+///
+/// Unlike [`crate::mpsc::bounded`], the returned `Receiver` can be cloned to
+/// give a channel multiple consumers.
+///
+/// The capacity must be a power of two.
+///
+/// # Panics
+///
+/// Panics if the capacity is not a power of two.
+pub fn bounded<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
+    let channel = Arc::new(RingBuf::new(cap));
+    let sender = Sender {
+        channel: Arc::clone(&channel),
+    };
+    let receiver = Receiver { channel };
+    (sender, receiver)
+}
+
+impl<T> Sender<T> {
+    /// Sends a value down the channel.
+    ///
+    /// This method will block if the channel's buffer is full.
+    ///
+    /// An error is returned if every receiver has been dropped.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if self.channel.receiver_count() == 0 {
+            return Err(SendError(value));
+        }
+
+        let mut current_value = value;
+        loop {
+            // Attempt to push the value into the ring buffer.
+            current_value = match self.channel.push(current_value) {
+                Ok(()) => return Ok(()),
+                Err(v) => v,
+            };
+
+            // The buffer is full. Register ourselves to be woken by the next
+            // `pop`, then re-check `push` once more: a slot may have freed up
+            // between our last attempt and registering, and without this
+            // second check we could park and miss the wakeup for it. We keep
+            // a clone of the token so we can deregister it below if our own
+            // re-check succeeds: with multiple producers, a stale token left
+            // in the queue would otherwise sit ahead of a genuinely parked
+            // producer and absorb the next `pop`'s wakeup instead of it.
+            let (wait_token, signal_token) = park::tokens();
+            self.channel.register_send_waiter(signal_token.clone());
+            current_value = match self.channel.push(current_value) {
+                Ok(()) => {
+                    self.channel.deregister_send_waiter(&signal_token);
+                    return Ok(());
+                }
+                Err(v) => v,
+            };
+
+            // After registering, we must re-check for disconnection.
+            if self.channel.receiver_count() == 0 {
+                self.channel.deregister_send_waiter(&signal_token);
+                return Err(SendError(current_value));
+            }
+
+            wait_token.wait();
+        }
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.channel.add_sender();
+        Sender {
+            channel: Arc::clone(&self.channel),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.channel.remove_sender();
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Receives a value from the channel.
+    ///
+    /// This method will block until a message is available.
+    ///
+    /// An error is returned if the channel is empty and all senders have been dropped.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        loop {
+            // Attempt to pop a value from the buffer.
+            if let Some(value) = self.channel.pop() {
+                return Ok(value);
+            }
+
+            // Buffer is empty. Check if senders are still connected.
+            if self.channel.sender_count() == 0 {
+                return Err(RecvError::Disconnected);
+            }
+
+            // Register ourselves to be woken by the next `push`, then
+            // re-check `pop` once more: a value may have arrived between our
+            // last attempt and registering, and without this second check we
+            // could park and miss the wakeup for it. We keep a clone of the
+            // token so we can deregister it below if our own re-check
+            // succeeds: with multiple consumers, a stale token left in the
+            // queue would otherwise sit ahead of a genuinely parked
+            // consumer and absorb the next `push`'s wakeup instead of it.
+            let (wait_token, signal_token) = park::tokens();
+            self.channel.register_recv_waiter(signal_token.clone());
+            if let Some(value) = self.channel.pop() {
+                self.channel.deregister_recv_waiter(&signal_token);
+                return Ok(value);
+            }
+            if self.channel.sender_count() == 0 {
+                self.channel.deregister_recv_waiter(&signal_token);
+                return Err(RecvError::Disconnected);
+            }
+
+            wait_token.wait();
+        }
+    }
+
+    /// Attempts to receive a value from the channel without blocking.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        match self.channel.pop() {
+            Some(value) => Ok(value),
+            None => {
+                // Buffer is empty. Check for disconnection.
+                if self.channel.sender_count() == 0 {
+                    Err(TryRecvError::Disconnected)
+                } else {
+                    Err(TryRecvError::Empty)
+                }
+            }
+        }
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        self.channel.add_receiver();
+        Receiver {
+            channel: Arc::clone(&self.channel),
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.channel.remove_receiver();
+    }
+}
@@ -0,0 +1,94 @@
+//! Optional `Future`/`Stream` interface, enabled by the `async` feature.
+
+use super::{Receiver, SendError, Sender};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+impl<T> Sender<T> {
+    /// Returns a future that resolves once `value` has been sent, without
+    /// blocking the calling thread while the buffer is full.
+    pub fn send_async(&self, value: T) -> SendFuture<'_, T> {
+        SendFuture {
+            sender: self,
+            value: Some(value),
+        }
+    }
+}
+
+/// A future returned by [`Sender::send_async`].
+pub struct SendFuture<'a, T> {
+    sender: &'a Sender<T>,
+    // `None` only after the future has resolved; polling again afterwards
+    // is a caller bug, same as polling any other completed future.
+    value: Option<T>,
+}
+
+// Nothing in `SendFuture` is self-referential, so it's fine to move even
+// while `T` itself might not be `Unpin`.
+impl<T> Unpin for SendFuture<'_, T> {}
+
+impl<T> Future for SendFuture<'_, T> {
+    type Output = Result<(), SendError<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let value = this
+            .value
+            .take()
+            .expect("SendFuture polled after it already resolved");
+
+        if this.sender.channel.receiver_count() == 0 {
+            return Poll::Ready(Err(SendError(value)));
+        }
+
+        let value = match this.sender.channel.push(value) {
+            Ok(()) => return Poll::Ready(Ok(())),
+            Err(value) => value,
+        };
+
+        // The buffer is full. Register to be woken by the next `pop`, then
+        // re-check `push` once more: a slot may have freed up between our
+        // last attempt and registering, and without this second check we
+        // could return `Pending` and miss the wakeup for it.
+        this.sender.channel.register_send_waker(cx.waker().clone());
+        let value = match this.sender.channel.push(value) {
+            Ok(()) => return Poll::Ready(Ok(())),
+            Err(value) => value,
+        };
+
+        if this.sender.channel.receiver_count() == 0 {
+            return Poll::Ready(Err(SendError(value)));
+        }
+
+        this.value = Some(value);
+        Poll::Pending
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        if let Some(value) = self.channel.pop() {
+            return Poll::Ready(Some(value));
+        }
+        if self.channel.sender_count() == 0 {
+            return Poll::Ready(None);
+        }
+
+        // Same check-register-check contract as `SendFuture::poll`: a value
+        // may have arrived between our last attempt and registering.
+        self.channel.register_recv_waker(cx.waker().clone());
+        if let Some(value) = self.channel.pop() {
+            return Poll::Ready(Some(value));
+        }
+        if self.channel.sender_count() == 0 {
+            return Poll::Ready(None);
+        }
+
+        Poll::Pending
+    }
+}
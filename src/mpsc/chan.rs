@@ -0,0 +1,120 @@
+#[cfg(feature = "async")]
+use std::task::Waker;
+
+use crate::park::SignalToken;
+use crate::ring_buf::RingBuf;
+use crate::unbounded_queue::UnboundedQueue;
+
+/// Internal storage for an `mpsc` channel.
+///
+/// `Sender`/`Receiver` don't know which flavor they're backed by; this lets
+/// `bounded()` and `unbounded()` share one pair of public types, the same
+/// way `std::sync::mpsc` dispatches over its channel flavors internally.
+pub(super) enum Chan<T> {
+    Bounded(RingBuf<T>),
+    Unbounded(UnboundedQueue<T>),
+}
+
+impl<T> Chan<T> {
+    pub(super) fn push(&self, value: T) -> Result<(), T> {
+        match self {
+            Chan::Bounded(rb) => rb.push(value),
+            Chan::Unbounded(q) => {
+                q.push(value);
+                Ok(())
+            }
+        }
+    }
+
+    pub(super) fn pop(&self) -> Option<T> {
+        match self {
+            Chan::Bounded(rb) => rb.pop(),
+            Chan::Unbounded(q) => q.pop(),
+        }
+    }
+
+    pub(super) fn register_recv_waiter(&self, token: SignalToken) {
+        match self {
+            Chan::Bounded(rb) => rb.register_recv_waiter(token),
+            Chan::Unbounded(q) => q.register_recv_waiter(token),
+        }
+    }
+
+    pub(super) fn register_send_waiter(&self, token: SignalToken) {
+        match self {
+            Chan::Bounded(rb) => rb.register_send_waiter(token),
+            // `push` never returns `Err` for an unbounded queue, so `send`
+            // never takes the "buffer full" branch that registers a waiter.
+            Chan::Unbounded(_) => unreachable!("unbounded send never blocks"),
+        }
+    }
+
+    pub(super) fn deregister_recv_waiter(&self, token: &SignalToken) {
+        match self {
+            Chan::Bounded(rb) => rb.deregister_recv_waiter(token),
+            Chan::Unbounded(q) => q.deregister_recv_waiter(token),
+        }
+    }
+
+    pub(super) fn deregister_send_waiter(&self, token: &SignalToken) {
+        match self {
+            Chan::Bounded(rb) => rb.deregister_send_waiter(token),
+            // Same reasoning as `register_send_waiter`: a send never parks
+            // on an unbounded queue, so there's never a waiter to remove.
+            Chan::Unbounded(_) => unreachable!("unbounded send never blocks"),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub(super) fn register_recv_waker(&self, waker: Waker) {
+        match self {
+            Chan::Bounded(rb) => rb.register_recv_waker(waker),
+            Chan::Unbounded(q) => q.register_recv_waker(waker),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub(super) fn register_send_waker(&self, waker: Waker) {
+        match self {
+            Chan::Bounded(rb) => rb.register_send_waker(waker),
+            // Same reasoning as `register_send_waiter`: `push` never fails
+            // for an unbounded queue, so this branch is never reached.
+            Chan::Unbounded(_) => unreachable!("unbounded send never blocks"),
+        }
+    }
+
+    pub(super) fn add_sender(&self) {
+        match self {
+            Chan::Bounded(rb) => rb.add_sender(),
+            Chan::Unbounded(q) => q.add_sender(),
+        }
+    }
+
+    pub(super) fn remove_sender(&self) -> usize {
+        match self {
+            Chan::Bounded(rb) => rb.remove_sender(),
+            Chan::Unbounded(q) => q.remove_sender(),
+        }
+    }
+
+    pub(super) fn sender_count(&self) -> usize {
+        match self {
+            Chan::Bounded(rb) => rb.sender_count(),
+            Chan::Unbounded(q) => q.sender_count(),
+        }
+    }
+
+    pub(super) fn remove_receiver(&self) -> usize {
+        match self {
+            Chan::Bounded(rb) => rb.remove_receiver(),
+            Chan::Unbounded(q) => q.remove_receiver(),
+        }
+    }
+
+    pub(super) fn receiver_count(&self) -> usize {
+        match self {
+            Chan::Bounded(rb) => rb.receiver_count(),
+            Chan::Unbounded(q) => q.receiver_count(),
+        }
+    }
+}
@@ -59,3 +59,54 @@ impl fmt::Display for TryRecvError {
 }
 
 impl Error for TryRecvError {}
+
+/// An error returned from the `send_timeout` method.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum SendTimeoutError<T> {
+    /// The value could not be sent before the timeout elapsed.
+    Timeout(T),
+    /// The value could not be sent because all receivers have been dropped.
+    Disconnected(T),
+}
+
+impl<T> fmt::Debug for SendTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SendTimeoutError::Timeout(..) => f.debug_tuple("Timeout").finish(),
+            SendTimeoutError::Disconnected(..) => f.debug_tuple("Disconnected").finish(),
+        }
+    }
+}
+
+impl<T> fmt::Display for SendTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SendTimeoutError::Timeout(..) => "timed out sending on a full channel".fmt(f),
+            SendTimeoutError::Disconnected(..) => "sending on a disconnected channel".fmt(f),
+        }
+    }
+}
+
+impl<T: Send> Error for SendTimeoutError<T> {}
+
+/// An error returned from the `recv_timeout` and `recv_deadline` methods.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RecvTimeoutError {
+    /// No value arrived before the timeout elapsed.
+    Timeout,
+    /// The channel is empty and disconnected.
+    Disconnected,
+}
+
+impl fmt::Display for RecvTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RecvTimeoutError::Timeout => "timed out waiting on an empty channel".fmt(f),
+            RecvTimeoutError::Disconnected => {
+                "receiving on an empty and disconnected channel".fmt(f)
+            }
+        }
+    }
+}
+
+impl Error for RecvTimeoutError {}
@@ -0,0 +1,48 @@
+use super::Receiver;
+
+/// A blocking iterator over a [`Receiver`]'s values.
+///
+/// Created by [`Receiver::iter`]. Each call to `next` blocks until a value
+/// is available, yielding `None` once the channel is empty and disconnected.
+pub struct Iter<'a, T> {
+    pub(super) receiver: &'a Receiver<T>,
+}
+
+impl<T> Iterator for Iter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.receiver.recv().ok()
+    }
+}
+
+/// A non-blocking iterator over a [`Receiver`]'s currently available values.
+///
+/// Created by [`Receiver::try_iter`]. Each call to `next` yields `None` as
+/// soon as the channel is empty, whether or not it is disconnected.
+pub struct TryIter<'a, T> {
+    pub(super) receiver: &'a Receiver<T>,
+}
+
+impl<T> Iterator for TryIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+/// An owning, blocking iterator over a [`Receiver`]'s values.
+///
+/// Created by [`Receiver::into_iter`] (e.g. via `for msg in receiver`).
+pub struct IntoIter<T> {
+    pub(super) receiver: Receiver<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.receiver.recv().ok()
+    }
+}
@@ -1,19 +1,32 @@
+#[cfg(feature = "async")]
+mod async_support;
+mod chan;
 mod error;
-mod ring_buf;
+mod iter;
+mod select;
 
-pub use error::{RecvError, SendError, TryRecvError};
+pub use error::{RecvError, RecvTimeoutError, SendError, SendTimeoutError, TryRecvError};
+pub use iter::{IntoIter, Iter, TryIter};
+pub use select::Select;
 
-use ring_buf::RingBuf;
-use std::{sync::Arc, thread};
+#[cfg(feature = "async")]
+pub use async_support::SendFuture;
+
+use chan::Chan;
+use crate::park;
+use crate::ring_buf::RingBuf;
+use crate::unbounded_queue::UnboundedQueue;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// The sending side of a channel.
 pub struct Sender<T> {
-    channel: Arc<RingBuf<T>>,
+    channel: Arc<Chan<T>>,
 }
 
 /// The receiving side of a channel.
 pub struct Receiver<T> {
-    channel: Arc<RingBuf<T>>,
+    channel: Arc<Chan<T>>,
 }
 
 /// Creates a new bounded MPSC channel with a specified capacity.
@@ -24,7 +37,21 @@ pub struct Receiver<T> {
 ///
 /// Panics if the capacity is not a power of two.
 pub fn bounded<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
-    let channel = Arc::new(RingBuf::new(cap));
+    let channel = Arc::new(Chan::Bounded(RingBuf::new(cap)));
+    let sender = Sender {
+        channel: Arc::clone(&channel),
+    };
+    let receiver = Receiver { channel };
+    (sender, receiver)
+}
+
+/// Creates a new unbounded MPSC channel.
+///
+/// `Sender::send` on the returned sender never blocks and never reports the
+/// channel as full; the underlying storage grows to hold whatever hasn't
+/// been received yet.
+pub fn unbounded<T>() -> (Sender<T>, Receiver<T>) {
+    let channel = Arc::new(Chan::Unbounded(UnboundedQueue::new()));
     let sender = Sender {
         channel: Arc::clone(&channel),
     };
@@ -39,29 +66,105 @@ impl<T> Sender<T> {
     ///
     /// An error is returned if the receiver has been dropped.
     pub fn send(&self, value: T) -> Result<(), SendError<T>> {
-        // To check for disconnection, we see if the Arc has only one reference left.
-        // If so, it must be this Sender, meaning the Receiver is gone.
-        // We use a relaxed ordering because we don't need to synchronize memory with this check.
-        if Arc::strong_count(&self.channel) == 1 {
+        if self.channel.receiver_count() == 0 {
             return Err(SendError(value));
         }
 
         let mut current_value = value;
         loop {
             // Attempt to push the value into the ring buffer.
-            match self.channel.push(current_value) {
+            current_value = match self.channel.push(current_value) {
+                Ok(()) => return Ok(()),
+                Err(v) => v,
+            };
+
+            // The buffer is full. Register ourselves to be woken by the next
+            // `pop`, then re-check `push` once more: a slot may have freed up
+            // between our last attempt and registering, and without this
+            // second check we could park and miss the wakeup for it. We keep
+            // a clone of the token so we can deregister it below on every
+            // exit that isn't a `signal()`-driven wakeup; otherwise a stale
+            // token left in the waiter queue could absorb a wakeup meant for
+            // someone else (see `deregister_send_waiter`).
+            let (wait_token, signal_token) = park::tokens();
+            self.channel.register_send_waiter(signal_token.clone());
+            current_value = match self.channel.push(current_value) {
+                Ok(()) => {
+                    self.channel.deregister_send_waiter(&signal_token);
+                    return Ok(());
+                }
+                Err(v) => v,
+            };
+
+            // After registering, we must re-check for disconnection.
+            if self.channel.receiver_count() == 0 {
+                self.channel.deregister_send_waiter(&signal_token);
+                return Err(SendError(current_value));
+            }
+
+            wait_token.wait();
+        }
+    }
+
+    /// Sends a value down the channel, waiting for up to `timeout` if the
+    /// buffer is full.
+    ///
+    /// On timeout, the value is returned inside `SendTimeoutError::Timeout`
+    /// so the caller can retry or otherwise recover it.
+    pub fn send_timeout(&self, value: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
+        self.send_deadline(value, Instant::now() + timeout)
+    }
+
+    /// Sends a value down the channel, waiting until `deadline` if the
+    /// buffer is full.
+    fn send_deadline(&self, value: T, deadline: Instant) -> Result<(), SendTimeoutError<T>> {
+        if self.channel.receiver_count() == 0 {
+            return Err(SendTimeoutError::Disconnected(value));
+        }
+
+        let mut current_value = value;
+        loop {
+            current_value = match self.channel.push(current_value) {
                 Ok(()) => return Ok(()),
-                Err(v) => {
-                    // The buffer is full. We store the value back and yield.
-                    current_value = v;
-                    thread::yield_now(); // Yield to allow the receiver to catch up.
-
-                    // After yielding, we must re-check for disconnection.
-                    if Arc::strong_count(&self.channel) == 1 {
-                        return Err(SendError(current_value));
-                    }
+                Err(v) => v,
+            };
+
+            if Instant::now() >= deadline {
+                return Err(SendTimeoutError::Timeout(current_value));
+            }
+
+            // We keep a clone of the token so we can deregister it below on
+            // every exit that isn't a `signal()`-driven wakeup; otherwise a
+            // stale token left in the waiter queue could absorb a wakeup
+            // meant for someone else (see `deregister_send_waiter`).
+            let (wait_token, signal_token) = park::tokens();
+            self.channel.register_send_waiter(signal_token.clone());
+            current_value = match self.channel.push(current_value) {
+                Ok(()) => {
+                    self.channel.deregister_send_waiter(&signal_token);
+                    return Ok(());
                 }
+                Err(v) => v,
+            };
+
+            if self.channel.receiver_count() == 0 {
+                self.channel.deregister_send_waiter(&signal_token);
+                return Err(SendTimeoutError::Disconnected(current_value));
             }
+
+            // `park_timeout` can return early, so the top of the loop
+            // re-checks `push` and the deadline rather than trusting that a
+            // wakeup means a slot is actually free. Either way, our token
+            // must come out of the waiter queue before we loop: if it was
+            // never signaled, a later `pop` could otherwise hand it a
+            // wakeup meant for someone else.
+            let now = Instant::now();
+            if now >= deadline {
+                self.channel.deregister_send_waiter(&signal_token);
+                return Err(SendTimeoutError::Timeout(current_value));
+            }
+            wait_token.wait_timeout(deadline - now);
+            self.channel.deregister_send_waiter(&signal_token);
         }
     }
 }
@@ -69,12 +172,19 @@ impl<T> Sender<T> {
 // Implement Clone to allow for multiple producers.
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Self {
+        self.channel.add_sender();
         Sender {
             channel: Arc::clone(&self.channel),
         }
     }
 }
 
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.channel.remove_sender();
+    }
+}
+
 impl<T> Receiver<T> {
     /// Receives a value from the channel.
     ///
@@ -84,18 +194,83 @@ impl<T> Receiver<T> {
     pub fn recv(&self) -> Result<T, RecvError> {
         loop {
             // Attempt to pop a value from the buffer.
-            match self.channel.pop() {
-                Some(value) => return Ok(value),
-                None => {
-                    // Buffer is empty. Check if senders are still connected.
-                    // If the strong count is 1, only this Receiver holds the Arc.
-                    if Arc::strong_count(&self.channel) == 1 {
-                        return Err(RecvError::Disconnected);
-                    }
-                    // Yield to allow senders to produce a message.
-                    thread::yield_now();
-                }
+            if let Some(value) = self.channel.pop() {
+                return Ok(value);
+            }
+
+            // Buffer is empty. Check if senders are still connected.
+            if self.channel.sender_count() == 0 {
+                return Err(RecvError::Disconnected);
+            }
+
+            // Register ourselves to be woken by the next `push`, then
+            // re-check `pop` once more: a value may have arrived between our
+            // last attempt and registering, and without this second check we
+            // could park and miss the wakeup for it. We keep a clone of the
+            // token so we can deregister it below on every exit that isn't a
+            // `signal()`-driven wakeup; otherwise a stale token left in the
+            // waiter queue could absorb a wakeup meant for someone else (see
+            // `deregister_recv_waiter`).
+            let (wait_token, signal_token) = park::tokens();
+            self.channel.register_recv_waiter(signal_token.clone());
+            if let Some(value) = self.channel.pop() {
+                self.channel.deregister_recv_waiter(&signal_token);
+                return Ok(value);
+            }
+            if self.channel.sender_count() == 0 {
+                self.channel.deregister_recv_waiter(&signal_token);
+                return Err(RecvError::Disconnected);
+            }
+
+            wait_token.wait();
+        }
+    }
+
+    /// Receives a value from the channel, waiting for up to `timeout` if
+    /// the buffer is empty.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        self.recv_deadline(Instant::now() + timeout)
+    }
+
+    /// Receives a value from the channel, waiting until `deadline` if the
+    /// buffer is empty.
+    pub fn recv_deadline(&self, deadline: Instant) -> Result<T, RecvTimeoutError> {
+        loop {
+            if let Some(value) = self.channel.pop() {
+                return Ok(value);
+            }
+            if self.channel.sender_count() == 0 {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(RecvTimeoutError::Timeout);
+            }
+
+            // We keep a clone of the token so we can deregister it below on
+            // every exit that isn't a `signal()`-driven wakeup; otherwise a
+            // stale token left in the waiter queue could absorb a wakeup
+            // meant for someone else (see `deregister_recv_waiter`).
+            let (wait_token, signal_token) = park::tokens();
+            self.channel.register_recv_waiter(signal_token.clone());
+            if let Some(value) = self.channel.pop() {
+                self.channel.deregister_recv_waiter(&signal_token);
+                return Ok(value);
+            }
+            if self.channel.sender_count() == 0 {
+                self.channel.deregister_recv_waiter(&signal_token);
+                return Err(RecvTimeoutError::Disconnected);
             }
+
+            // `park_timeout` can return early, so the top of the loop
+            // re-checks `pop` and the deadline rather than trusting that a
+            // wakeup means a value actually arrived. Either way, our token
+            // must come out of the waiter queue before we loop: if it was
+            // never signaled, a later `push` could otherwise hand it a
+            // wakeup meant for someone else.
+            wait_token.wait_timeout(deadline - now);
+            self.channel.deregister_recv_waiter(&signal_token);
         }
     }
 
@@ -105,7 +280,7 @@ impl<T> Receiver<T> {
             Some(value) => Ok(value),
             None => {
                 // Buffer is empty. Check for disconnection.
-                if Arc::strong_count(&self.channel) == 1 {
+                if self.channel.sender_count() == 0 {
                     Err(TryRecvError::Disconnected)
                 } else {
                     Err(TryRecvError::Empty)
@@ -113,4 +288,45 @@ impl<T> Receiver<T> {
             }
         }
     }
+
+    /// Returns a blocking iterator over the channel's values.
+    ///
+    /// Each call to `next` behaves like `recv`, yielding `None` once the
+    /// channel is empty and disconnected.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { receiver: self }
+    }
+
+    /// Returns a non-blocking iterator over the channel's currently
+    /// available values.
+    ///
+    /// Each call to `next` behaves like `try_recv`, yielding `None` as soon
+    /// as the channel is empty, whether or not it is disconnected.
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter { receiver: self }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.channel.remove_receiver();
+    }
+}
+
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { receiver: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
 }
@@ -0,0 +1,119 @@
+use super::{park, Receiver, RecvError, TryRecvError};
+
+/// Waits on several [`Receiver`]s at once and proceeds with whichever
+/// becomes ready first.
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut select = Select::new();
+/// let a = select.add(&rx_a);
+/// let b = select.add(&rx_b);
+/// match select.recv() {
+///     Ok((i, value)) if i == a => { /* from rx_a */ }
+///     Ok((i, value)) if i == b => { /* from rx_b */ }
+///     _ => {}
+/// }
+/// ```
+pub struct Select<'a, T> {
+    receivers: Vec<&'a Receiver<T>>,
+}
+
+impl<'a, T> Select<'a, T> {
+    /// Creates an empty `Select` with no registered channels.
+    pub fn new() -> Self {
+        Self {
+            receivers: Vec::new(),
+        }
+    }
+
+    /// Registers a channel and returns the index `recv` will report it as.
+    pub fn add(&mut self, receiver: &'a Receiver<T>) -> usize {
+        self.receivers.push(receiver);
+        self.receivers.len() - 1
+    }
+
+    /// Blocks until one of the registered channels has a value (or all are
+    /// disconnected), returning its index and the value.
+    ///
+    /// An error is returned once every registered channel is empty and
+    /// disconnected; a disconnected-but-still-readable channel is treated
+    /// the same as a connected one until it's drained.
+    pub fn recv(&self) -> Result<(usize, T), RecvError> {
+        loop {
+            if let Some(ready) = self.poll() {
+                return ready;
+            }
+
+            // Every channel was empty. Register one shared wait token with
+            // each of them so that a `push` on any one of them wakes us,
+            // then poll once more: a value may have arrived on any channel
+            // between our last attempt and registering, and without this
+            // second check we could park and miss the wakeup for it. A
+            // channel only drains the token from its own queue when it
+            // fires, so `_registration` deregisters it from every channel
+            // on the way out — on our own poll succeeding, on the wakeup,
+            // or on unwinding.
+            let (wait_token, signal_token) = park::tokens();
+            for receiver in &self.receivers {
+                receiver.channel.register_recv_waiter(signal_token.clone());
+            }
+            let _registration = Registration {
+                receivers: &self.receivers,
+                token: signal_token,
+            };
+
+            if let Some(ready) = self.poll() {
+                return ready;
+            }
+
+            wait_token.wait();
+        }
+    }
+
+    /// Tries every registered channel once. Returns `Some(Ok(..))` on the
+    /// first value found, `Some(Err(Disconnected))` once none are left, or
+    /// `None` if at least one channel is still connected but empty.
+    fn poll(&self) -> Option<Result<(usize, T), RecvError>> {
+        let mut any_connected = false;
+        for (index, receiver) in self.receivers.iter().enumerate() {
+            match receiver.try_recv() {
+                Ok(value) => return Some(Ok((index, value))),
+                Err(TryRecvError::Empty) => any_connected = true,
+                Err(TryRecvError::Disconnected) => {}
+            }
+        }
+        if any_connected {
+            None
+        } else {
+            Some(Err(RecvError::Disconnected))
+        }
+    }
+}
+
+impl<T> Default for Select<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Deregisters `token` from every receiver in `receivers` when dropped.
+///
+/// The same token is registered with every channel in a `Select::recv`
+/// call, and each channel only pops it from its own queue when that channel
+/// fires. Without this, the token would linger in the queues of the
+/// channels that did not fire, and a later
+/// `push` on one of them could pop and signal it instead of a thread that's
+/// actually parked on it.
+struct Registration<'a, 'b, T> {
+    receivers: &'b [&'a Receiver<T>],
+    token: park::SignalToken,
+}
+
+impl<T> Drop for Registration<'_, '_, T> {
+    fn drop(&mut self) {
+        for receiver in self.receivers {
+            receiver.channel.deregister_recv_waiter(&self.token);
+        }
+    }
+}
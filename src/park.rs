@@ -0,0 +1,66 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+/// State shared between a `WaitToken` and its paired `SignalToken`.
+struct Inner {
+    thread: Thread,
+    woken: AtomicBool,
+}
+
+/// Held by the thread that wants to be parked until woken.
+pub(crate) struct WaitToken(Arc<Inner>);
+
+/// Held by whoever may need to wake the paired `WaitToken`'s thread.
+#[derive(Clone)]
+pub(crate) struct SignalToken(Arc<Inner>);
+
+/// Creates a linked pair for a single wait/wake cycle: the current thread
+/// parks behind the returned `WaitToken`, and is woken through the paired
+/// `SignalToken`, which may be handed off to another thread.
+pub(crate) fn tokens() -> (WaitToken, SignalToken) {
+    let inner = Arc::new(Inner {
+        thread: thread::current(),
+        woken: AtomicBool::new(false),
+    });
+    (WaitToken(Arc::clone(&inner)), SignalToken(inner))
+}
+
+impl WaitToken {
+    /// Parks the current thread until `signal()` is called on the paired
+    /// `SignalToken`. Loops to guard against spurious `park()` wakeups.
+    pub(crate) fn wait(self) {
+        while !self.0.woken.load(Ordering::Acquire) {
+            thread::park();
+        }
+    }
+
+    /// Parks the current thread for up to `timeout`, or returns immediately
+    /// if already signaled. `park_timeout` can wake up early for reasons
+    /// unrelated to the signal, so callers must re-check their own wait
+    /// condition afterwards rather than assuming the timeout elapsed.
+    pub(crate) fn wait_timeout(self, timeout: Duration) {
+        if !self.0.woken.load(Ordering::Acquire) {
+            thread::park_timeout(timeout);
+        }
+    }
+}
+
+impl SignalToken {
+    /// Wakes the thread that is (or is about to be) parked on the paired
+    /// `WaitToken`. `woken` is set before unparking so that a `wait()` that
+    /// hasn't parked yet still observes the signal instead of sleeping
+    /// forever.
+    pub(crate) fn signal(&self) {
+        self.0.woken.store(true, Ordering::Release);
+        self.0.thread.unpark();
+    }
+
+    /// Returns `true` if `self` and `other` refer to the same wait/wake
+    /// pair, i.e. one was cloned from the other. Used to pick a specific
+    /// token back out of a waiter queue for deregistration.
+    pub(crate) fn same(&self, other: &SignalToken) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
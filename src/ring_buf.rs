@@ -0,0 +1,335 @@
+use std::cell::UnsafeCell;
+use std::collections::VecDeque;
+use std::mem::MaybeUninit;
+// NOTE: AtomicUsize is only available on platforms that support atomic
+//       loads and stores of usize.
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+#[cfg(feature = "async")]
+use std::task::Waker;
+
+use crate::park::SignalToken;
+
+/// A single slot holding one value, shared by the bounded ring buffer and
+/// the unbounded queue's blocks.
+pub(crate) struct Slot<T> {
+    // MaybeUninit means "raw bytes big enough for T"
+    // Use UnsafeCell for interior mutability
+    pub(crate) data: UnsafeCell<MaybeUninit<T>>,
+    pub(crate) empty: AtomicBool,
+}
+
+impl<T> Slot<T> {
+    // The `const fn` lets us build an array of uninitialized slots at compile time
+    // without needing `unsafe`.
+    pub(crate) const fn new() -> Self {
+        Self {
+            data: UnsafeCell::new(MaybeUninit::uninit()),
+            empty: AtomicBool::new(true),
+        }
+    }
+}
+
+unsafe impl<T: Send> Send for Slot<T> {}
+unsafe impl<T: Send> Sync for Slot<T> {}
+
+/// Lock-free, multi-producer multi-consumer ring buffer.
+pub(crate) struct RingBuf<T> {
+    // A heap-allocated array of size CAP
+    buf: Box<[Slot<T>]>,
+    // Next write position (producers)
+    head: AtomicUsize,
+    mask: usize,
+    // Next read  position (comsumer)
+    tail: AtomicUsize,
+    // Parked consumers waiting for a slot to become non-empty.
+    recv_waiters: Mutex<VecDeque<SignalToken>>,
+    // Parked producers waiting for a slot to become empty.
+    send_waiters: Mutex<VecDeque<SignalToken>>,
+    // Async task waiting on the same condition as `recv_waiters`, but via a
+    // `Waker` instead of parking a thread. Only the most recently registered
+    // waker is kept: a later `poll` replaces whatever was there, so a
+    // cancelled or spuriously-repolled future can't leave a dead waker
+    // behind for good. A single slot is correct here because mpsc has one
+    // receiver.
+    #[cfg(feature = "async")]
+    recv_waker: Mutex<Option<Waker>>,
+    // Async tasks waiting on the same condition as `send_waiters`, but via a
+    // `Waker` instead of parking a thread. Unlike `recv_waker`, this is a
+    // list: mpsc is multi-producer, so more than one `send_async` future can
+    // be parked on a full buffer at once, and a single slot would let one
+    // overwrite (and starve) another.
+    #[cfg(feature = "async")]
+    send_wakers: Mutex<VecDeque<Waker>>,
+    // Live `Sender`/`Receiver` handles, tracked explicitly so that
+    // disconnection can be detected even when senders are cloned (an
+    // `Arc::strong_count` check can't tell "many senders" from "a sender
+    // plus the receiver").
+    senders: AtomicUsize,
+    receivers: AtomicUsize,
+}
+
+impl<T> RingBuf<T> {
+    pub(crate) fn new(cap: usize) -> Self {
+        // Power-of-two capacity for performant masking
+        // A slow modulo (`% CAP`) can now be a fast mitmask (`& CAP`) when wrapping indices.
+        assert!(cap.is_power_of_two(), "CAP must be 2^n");
+
+        let mut v = Vec::with_capacity(cap);
+        v.resize_with(cap, || Slot::new());
+        Self {
+            buf: v.into_boxed_slice(),
+            head: AtomicUsize::new(0),
+            mask: cap - 1,
+            tail: AtomicUsize::new(0),
+            recv_waiters: Mutex::new(VecDeque::new()),
+            send_waiters: Mutex::new(VecDeque::new()),
+            #[cfg(feature = "async")]
+            recv_waker: Mutex::new(None),
+            #[cfg(feature = "async")]
+            send_wakers: Mutex::new(VecDeque::new()),
+            senders: AtomicUsize::new(1),
+            receivers: AtomicUsize::new(1),
+        }
+    }
+
+    pub(crate) fn add_sender(&self) {
+        self.senders.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Decrements the sender count, returning the new count. When it
+    /// reaches zero, any parked consumer is woken so it can observe
+    /// disconnection instead of waiting forever for a value that will
+    /// never come.
+    pub(crate) fn remove_sender(&self) -> usize {
+        let remaining = self.senders.fetch_sub(1, Ordering::AcqRel) - 1;
+        if remaining == 0 {
+            self.wake_all_receivers();
+        }
+        remaining
+    }
+
+    pub(crate) fn sender_count(&self) -> usize {
+        self.senders.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn add_receiver(&self) {
+        self.receivers.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Decrements the receiver count, returning the new count. When it
+    /// reaches zero, every parked producer is woken so each can observe
+    /// disconnection instead of waiting forever for a slot that will never
+    /// be freed.
+    pub(crate) fn remove_receiver(&self) -> usize {
+        let remaining = self.receivers.fetch_sub(1, Ordering::AcqRel) - 1;
+        if remaining == 0 {
+            self.wake_all_senders();
+        }
+        remaining
+    }
+
+    pub(crate) fn receiver_count(&self) -> usize {
+        self.receivers.load(Ordering::Acquire)
+    }
+
+    /// Registers `token` to be woken the next time a slot becomes non-empty.
+    ///
+    /// Callers must re-check `pop()` after registering (and before parking)
+    /// to avoid a lost wakeup: a `push` that landed between the last `pop()`
+    /// attempt and this registration would otherwise go unnoticed.
+    pub(crate) fn register_recv_waiter(&self, token: SignalToken) {
+        self.recv_waiters.lock().unwrap().push_back(token);
+    }
+
+    /// Registers `token` to be woken the next time a slot becomes empty.
+    ///
+    /// Same check-register-check contract as `register_recv_waiter`.
+    pub(crate) fn register_send_waiter(&self, token: SignalToken) {
+        self.send_waiters.lock().unwrap().push_back(token);
+    }
+
+    /// Removes `token` from the receive waiter queue, if it's still there.
+    ///
+    /// Callers that registered a token must call this on every exit that
+    /// isn't a `signal()`-driven wakeup (a successful re-check, a timeout, a
+    /// disconnect). Otherwise the stale token is left at the front of the
+    /// queue, where the next `push` pops and signals it instead of a thread
+    /// that's actually parked — a lost wakeup.
+    pub(crate) fn deregister_recv_waiter(&self, token: &SignalToken) {
+        self.recv_waiters.lock().unwrap().retain(|t| !t.same(token));
+    }
+
+    /// Same contract as `deregister_recv_waiter`, for producers.
+    pub(crate) fn deregister_send_waiter(&self, token: &SignalToken) {
+        self.send_waiters.lock().unwrap().retain(|t| !t.same(token));
+    }
+
+    /// Registers `waker` to be woken the next time a slot becomes non-empty,
+    /// replacing whatever waker was previously registered.
+    ///
+    /// Same check-register-check contract as `register_recv_waiter`.
+    #[cfg(feature = "async")]
+    pub(crate) fn register_recv_waker(&self, waker: Waker) {
+        *self.recv_waker.lock().unwrap() = Some(waker);
+    }
+
+    /// Registers `waker` to be woken the next time a slot becomes empty.
+    ///
+    /// Same check-register-check contract as `register_recv_waiter`. Unlike
+    /// `register_recv_waker`, this keeps one entry per distinct pending
+    /// future rather than a single slot: mpsc is multi-producer, so more
+    /// than one `send_async` future can be pending on a full buffer at once,
+    /// and each needs its own waker woken. A future that's re-polled while
+    /// still pending (as combinators like `select!`/`FuturesUnordered` do)
+    /// re-registers the same waker, so any existing entry that would wake
+    /// the same task is replaced in place instead of piling up a duplicate.
+    #[cfg(feature = "async")]
+    pub(crate) fn register_send_waker(&self, waker: Waker) {
+        let mut wakers = self.send_wakers.lock().unwrap();
+        if let Some(existing) = wakers.iter_mut().find(|w| w.will_wake(&waker)) {
+            *existing = waker;
+        } else {
+            wakers.push_back(waker);
+        }
+    }
+
+    fn wake_receiver(&self) {
+        if let Some(token) = self.recv_waiters.lock().unwrap().pop_front() {
+            token.signal();
+        }
+        #[cfg(feature = "async")]
+        if let Some(waker) = self.recv_waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+
+    fn wake_all_receivers(&self) {
+        for token in self.recv_waiters.lock().unwrap().drain(..) {
+            token.signal();
+        }
+        #[cfg(feature = "async")]
+        if let Some(waker) = self.recv_waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+
+    fn wake_sender(&self) {
+        if let Some(token) = self.send_waiters.lock().unwrap().pop_front() {
+            token.signal();
+        }
+        #[cfg(feature = "async")]
+        if let Some(waker) = self.send_wakers.lock().unwrap().pop_front() {
+            waker.wake();
+        }
+    }
+
+    fn wake_all_senders(&self) {
+        for token in self.send_waiters.lock().unwrap().drain(..) {
+            token.signal();
+        }
+        #[cfg(feature = "async")]
+        for waker in self.send_wakers.lock().unwrap().drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// Producers push. Returns `Err(value)` when the buffer is full.
+    pub(crate) fn push(&self, value: T) -> Result<(), T> {
+        loop {
+            // Load the current head to check the corresponding slot.
+            let current_head = self.head.load(Ordering::Relaxed);
+            let pos = current_head & self.mask;
+            let slot = &self.buf[pos];
+
+            // Check if the slot is empty.
+            if !slot.empty.load(Ordering::Acquire) {
+                // The slot is occupied. Before returning an error, we should check if
+                // the buffer is actually full, as the consumer might be about to free a slot.
+                // A simple check is to see if the head has lapped the tail.
+                let tail_pos = self.tail.load(Ordering::Relaxed);
+                if current_head.wrapping_sub(tail_pos) >= self.buf.len() {
+                    return Err(value); // Buffer is genuinely full.
+                }
+                // If not full, another producer is likely using this slot, or we are waiting
+                // for the consumer. Yielding can be polite, or just loop again.
+                std::hint::spin_loop(); // or thread::yield_now();
+                continue;
+            }
+
+            // Try to atomically claim this slot by incrementing head.
+            // We use compare_exchange to ensure we only update it if it hasn't changed.
+            match self.head.compare_exchange(
+                current_head,
+                current_head.wrapping_add(1),
+                Ordering::Release, // Use Release on success to sync with the write below.
+                Ordering::Relaxed, // Use Relaxed on failure, we'll just loop again.
+            ) {
+                Ok(_) => {
+                    // Success! We have exclusive access to this slot.
+                    // SAFETY: We are the only thread that can succeed in the CAS for this `pos`.
+                    unsafe { (*slot.data.get()).write(value) };
+                    slot.empty.store(false, Ordering::Release);
+                    // A consumer may be parked waiting for exactly this slot.
+                    self.wake_receiver();
+                    return Ok(());
+                }
+                Err(_) => {
+                    // Another producer beat us to it. Loop and try again.
+                    continue;
+                }
+            }
+        }
+    }
+
+    /// Consumers pop. Returns `None` if the buffer is empty.
+    pub(crate) fn pop(&self) -> Option<T> {
+        loop {
+            // Load the current tail to check the corresponding slot.
+            let current_tail = self.tail.load(Ordering::Relaxed);
+            let pos = current_tail & self.mask;
+            let slot = &self.buf[pos];
+
+            // Check if the slot has something to read.
+            if slot.empty.load(Ordering::Acquire) {
+                // The slot looks empty. Before returning `None`, we should check if
+                // the buffer is actually empty, as a producer might be about to fill it.
+                let head_pos = self.head.load(Ordering::Relaxed);
+                if head_pos.wrapping_sub(current_tail) == 0 {
+                    return None; // Buffer is genuinely empty.
+                }
+                // If not empty, a producer has claimed this slot but hasn't
+                // finished writing yet, or another consumer already claimed
+                // it and is mid-read. Loop again.
+                std::hint::spin_loop();
+                continue;
+            }
+
+            // Try to atomically claim this slot by incrementing tail.
+            // This mirrors the producer side of `push`: only the consumer
+            // that wins the CAS reads the slot, so two consumers can never
+            // observe the same value.
+            match self.tail.compare_exchange(
+                current_tail,
+                current_tail.wrapping_add(1),
+                Ordering::Release, // Use Release on success to sync with the read below.
+                Ordering::Relaxed, // Use Relaxed on failure, we'll just loop again.
+            ) {
+                Ok(_) => {
+                    // Success! We have exclusive access to this slot.
+                    // SAFETY: We are the only thread that can succeed in the CAS for this
+                    //         `pos`, so `assume_init_read` is called exactly once.
+                    let v = unsafe { (*slot.data.get()).assume_init_read() };
+                    slot.empty.store(true, Ordering::Release);
+                    // A producer may be parked waiting for exactly this slot to free up.
+                    self.wake_sender();
+                    return Some(v);
+                }
+                Err(_) => {
+                    // Another consumer beat us to it. Loop and try again.
+                    continue;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+#[cfg(feature = "async")]
+use std::task::Waker;
+
+use crate::park::SignalToken;
+use crate::ring_buf::Slot;
+
+/// Number of slots per block. Kept small so a block is cheap to allocate on
+/// demand, matching the ring buffer's smallest practical capacity.
+const BLOCK_CAP: usize = 32;
+
+/// A fixed-size chunk of slots. The queue is a chain of these, grown one
+/// block at a time as producers run past the end of it.
+struct Block<T> {
+    slots: [Slot<T>; BLOCK_CAP],
+}
+
+impl<T> Block<T> {
+    fn new_boxed() -> Box<Self> {
+        Box::new(Self {
+            slots: std::array::from_fn(|_| Slot::new()),
+        })
+    }
+}
+
+unsafe impl<T: Send> Send for Block<T> {}
+unsafe impl<T: Send> Sync for Block<T> {}
+
+/// Live blocks, in order, starting with the one that holds `read_index`.
+/// `base` is the block number of `blocks[0]`.
+struct BlockChain<T> {
+    blocks: VecDeque<Box<Block<T>>>,
+    base: usize,
+}
+
+/// Unbounded multi-producer, single-consumer queue backed by a chain of
+/// fixed-size blocks.
+///
+/// Each slot is written and read using the same `MaybeUninit` + `AtomicBool`
+/// discipline as `RingBuf`. The chain itself sits behind the `blocks` lock:
+/// every push and every pop takes it briefly to look up the block for its
+/// index, a push that runs past the end of the chain allocates the next
+/// block under it, and the pop that drains a block's last slot reclaims
+/// that block under it. Pushes and pops are therefore not lock-free.
+pub(crate) struct UnboundedQueue<T> {
+    write_index: AtomicUsize,
+    read_index: AtomicUsize,
+    blocks: Mutex<BlockChain<T>>,
+    // Parked consumer waiting for a value to become available.
+    recv_waiters: Mutex<VecDeque<SignalToken>>,
+    // Async task waiting on the same condition as `recv_waiters`, but via a
+    // `Waker` instead of parking a thread. Only the most recently registered
+    // waker is kept: a later `poll` replaces whatever was there, so a
+    // cancelled or spuriously-repolled future can't leave a dead waker
+    // behind for good.
+    #[cfg(feature = "async")]
+    recv_waker: Mutex<Option<Waker>>,
+    senders: AtomicUsize,
+    receivers: AtomicUsize,
+}
+
+impl<T> UnboundedQueue<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            write_index: AtomicUsize::new(0),
+            read_index: AtomicUsize::new(0),
+            blocks: Mutex::new(BlockChain {
+                blocks: VecDeque::from([Block::new_boxed()]),
+                base: 0,
+            }),
+            recv_waiters: Mutex::new(VecDeque::new()),
+            #[cfg(feature = "async")]
+            recv_waker: Mutex::new(None),
+            senders: AtomicUsize::new(1),
+            receivers: AtomicUsize::new(1),
+        }
+    }
+
+    /// Returns a raw pointer to the block holding `block_no`, allocating and
+    /// linking blocks up to it if needed.
+    fn block_for_write(&self, block_no: usize) -> *const Block<T> {
+        let mut chain = self.blocks.lock().unwrap();
+        while chain.base + chain.blocks.len() <= block_no {
+            chain.blocks.push_back(Block::new_boxed());
+        }
+        &*chain.blocks[block_no - chain.base] as *const Block<T>
+    }
+
+    /// Returns a raw pointer to the block holding `block_no`, or `None` if
+    /// it hasn't been allocated yet (nothing has been pushed that far).
+    fn block_for_read(&self, block_no: usize) -> Option<*const Block<T>> {
+        let chain = self.blocks.lock().unwrap();
+        if block_no < chain.base {
+            return None;
+        }
+        chain
+            .blocks
+            .get(block_no - chain.base)
+            .map(|b| &**b as *const Block<T>)
+    }
+
+    /// Drops every block up to and including `block_no`. Only called once
+    /// every slot in `block_no` has been both written and consumed, so no
+    /// producer can still be holding a pointer into it.
+    fn reclaim_through(&self, block_no: usize) {
+        let mut chain = self.blocks.lock().unwrap();
+        while chain.base <= block_no {
+            chain.blocks.pop_front();
+            chain.base += 1;
+        }
+    }
+
+    pub(crate) fn add_sender(&self) {
+        self.senders.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn remove_sender(&self) -> usize {
+        let remaining = self.senders.fetch_sub(1, Ordering::AcqRel) - 1;
+        if remaining == 0 {
+            self.wake_all_receivers();
+        }
+        remaining
+    }
+
+    pub(crate) fn sender_count(&self) -> usize {
+        self.senders.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn remove_receiver(&self) -> usize {
+        self.receivers.fetch_sub(1, Ordering::AcqRel) - 1
+    }
+
+    pub(crate) fn receiver_count(&self) -> usize {
+        self.receivers.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn register_recv_waiter(&self, token: SignalToken) {
+        self.recv_waiters.lock().unwrap().push_back(token);
+    }
+
+    /// Removes `token` from the receive waiter queue, if it's still there.
+    ///
+    /// Same contract as `RingBuf::deregister_recv_waiter`: callers must call
+    /// this on every exit that isn't a `signal()`-driven wakeup, or a stale
+    /// token left in the queue can absorb a wakeup meant for someone else.
+    pub(crate) fn deregister_recv_waiter(&self, token: &SignalToken) {
+        self.recv_waiters.lock().unwrap().retain(|t| !t.same(token));
+    }
+
+    /// Registers `waker` to be woken the next time a value becomes
+    /// available, replacing whatever waker was previously registered.
+    #[cfg(feature = "async")]
+    pub(crate) fn register_recv_waker(&self, waker: Waker) {
+        *self.recv_waker.lock().unwrap() = Some(waker);
+    }
+
+    fn wake_receiver(&self) {
+        if let Some(token) = self.recv_waiters.lock().unwrap().pop_front() {
+            token.signal();
+        }
+        #[cfg(feature = "async")]
+        if let Some(waker) = self.recv_waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+
+    fn wake_all_receivers(&self) {
+        for token in self.recv_waiters.lock().unwrap().drain(..) {
+            token.signal();
+        }
+        #[cfg(feature = "async")]
+        if let Some(waker) = self.recv_waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+
+    /// Pushes a value. This never blocks and never fails on account of
+    /// capacity; the queue grows a block at a time as needed.
+    pub(crate) fn push(&self, value: T) {
+        let idx = self.write_index.fetch_add(1, Ordering::Relaxed);
+        let block_no = idx / BLOCK_CAP;
+        let slot_no = idx % BLOCK_CAP;
+        let block = self.block_for_write(block_no);
+
+        // SAFETY: `idx` came from a `fetch_add`, so it is handed to exactly
+        // one caller; the targeted block stays alive at least until this
+        // slot (and every other slot in it) has been read back out.
+        let slot = unsafe { &(*block).slots[slot_no] };
+        unsafe { (*slot.data.get()).write(value) };
+        slot.empty.store(false, Ordering::Release);
+
+        self.wake_receiver();
+    }
+
+    /// Pops a value. Returns `None` if nothing has been pushed yet.
+    pub(crate) fn pop(&self) -> Option<T> {
+        let idx = self.read_index.load(Ordering::Relaxed);
+        let block_no = idx / BLOCK_CAP;
+        let slot_no = idx % BLOCK_CAP;
+        let block = self.block_for_read(block_no)?;
+
+        // SAFETY: `block` was resolved under the chain lock above and is
+        // kept alive by `reclaim_through` only ever running behind the
+        // reader.
+        let slot = unsafe { &(*block).slots[slot_no] };
+        if slot.empty.load(Ordering::Acquire) {
+            return None; // Claimed by a producer but not written yet.
+        }
+
+        // SAFETY: only this consumer advances `read_index`, so each slot is
+        // read back exactly once.
+        let v = unsafe { (*slot.data.get()).assume_init_read() };
+        slot.empty.store(true, Ordering::Release);
+        self.read_index.store(idx + 1, Ordering::Relaxed);
+
+        if slot_no == BLOCK_CAP - 1 {
+            self.reclaim_through(block_no);
+        }
+
+        Some(v)
+    }
+}